@@ -0,0 +1,715 @@
+#![feature(portable_simd)]
+
+use base64::encode;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+pub const ROUNDS: usize = 256; // Number of rounds
+const PRIME: u128 = 0x100000001b3; // A large prime number
+
+/// The 256-bit state as a single SIMD vector, plus the scalar rounds that
+/// mix it. `advanced_hash` and `Mphc256` never spawn a thread per byte: the
+/// state is one evolving vector that each byte is absorbed into
+/// sequentially, which is both much faster and makes the digest depend on
+/// byte order (unlike XOR-folding independent per-byte thread results).
+///
+/// `simd_impl` and `scalar_impl` must compute bit-identical output for the
+/// same input (see the `simd_and_scalar_backends_match` test below) so the
+/// digest doesn't change depending on `target_arch`; both are always
+/// compiled so that invariant can actually be checked.
+/// Both backends are compiled on every target (not just the one selected as
+/// `simd_backend` below) so `simd_and_scalar_backends_match` can hold them
+/// to the same bit-for-bit output; outside of that test, whichever one
+/// isn't the active `simd_backend` for this target is naturally unused.
+#[allow(dead_code)]
+mod simd_impl {
+    use super::PRIME;
+    use std::simd::{simd_swizzle, u64x4};
+
+    pub type State = u64x4;
+
+    pub fn initial_state() -> State {
+        u64x4::from_array([
+            0x6a09e667f3bcc908,
+            0xbb67ae8584caa73b,
+            0x3c6ef372fe94f82b,
+            0xa54ff53a5f1d36f1,
+        ])
+    }
+
+    fn rotate_left(v: State, amounts: [u32; 4]) -> State {
+        let lanes = v.to_array();
+        State::from_array([
+            lanes[0].rotate_left(amounts[0]),
+            lanes[1].rotate_left(amounts[1]),
+            lanes[2].rotate_left(amounts[2]),
+            lanes[3].rotate_left(amounts[3]),
+        ])
+    }
+
+    /// Modular non-linear transformation, lane-wise across the vector.
+    fn non_linear_transform(v: State) -> State {
+        let rotated = rotate_left(v, [64 - 23; 4]); // rotate_right(23)
+        (v ^ rotated) * State::splat(PRIME as u64)
+    }
+
+    /// Cross-lane mixing by rotating the vector's own lanes, replacing the
+    /// scalar `state[i] ^= state[(i + 1) % 4]` step. Runs after the vector's
+    /// xor/add/rotate/non_linear steps, so it mixes in each neighbor's
+    /// *post-transform* value.
+    fn rotate_lanes(v: State) -> State {
+        simd_swizzle!(v, [1, 2, 3, 0])
+    }
+
+    pub fn hash_round(state: &mut State, mixed_input: u64, round: usize) {
+        *state ^= State::splat(mixed_input);
+        *state += State::splat(PRIME as u64);
+        let amounts = [
+            (round % 16) as u32,
+            ((round % 16) + 8) as u32,
+            ((round % 16) + 16) as u32,
+            ((round % 16) + 24) as u32,
+        ];
+        *state = rotate_left(*state, amounts);
+        *state = non_linear_transform(*state);
+        *state ^= rotate_lanes(*state);
+    }
+
+    pub fn to_bytes(state: &State) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, lane) in state.to_array().iter().enumerate() {
+            out[i * 8..(i + 1) * 8].copy_from_slice(&lane.to_le_bytes());
+        }
+        out
+    }
+}
+
+/// Scalar fallback for targets without a portable_simd backend: the same
+/// per-lane math as `simd_impl`, just without the vector type. The
+/// cross-mix step snapshots every lane's post-`non_linear_transform` value
+/// before XOR-ing in its `(i + 1) % 4` neighbor, mirroring `rotate_lanes`
+/// operating on the already-updated SIMD vector rather than on
+/// still-being-mutated scalar lanes.
+#[allow(dead_code)]
+mod scalar_impl {
+    use super::PRIME;
+
+    pub type State = [u64; 4];
+
+    pub fn initial_state() -> State {
+        [
+            0x6a09e667f3bcc908,
+            0xbb67ae8584caa73b,
+            0x3c6ef372fe94f82b,
+            0xa54ff53a5f1d36f1,
+        ]
+    }
+
+    fn non_linear_transform(value: u64) -> u64 {
+        let rotated = value.rotate_right(23);
+        (value ^ rotated).wrapping_mul(PRIME as u64)
+    }
+
+    pub fn hash_round(state: &mut State, mixed_input: u64, round: usize) {
+        let mut next = *state;
+        for (i, lane) in next.iter_mut().enumerate() {
+            *lane ^= mixed_input;
+            *lane = lane.wrapping_add(PRIME as u64);
+            *lane = lane.rotate_left((round % 16 + i * 8) as u32);
+            *lane = non_linear_transform(*lane);
+        }
+        let snapshot = next;
+        for (i, lane) in next.iter_mut().enumerate() {
+            *lane ^= snapshot[(i + 1) % 4];
+        }
+        *state = next;
+    }
+
+    pub fn to_bytes(state: &State) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, lane) in state.iter().enumerate() {
+            out[i * 8..(i + 1) * 8].copy_from_slice(&lane.to_le_bytes());
+        }
+        out
+    }
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "wasm32"))]
+use simd_impl as simd_backend;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "wasm32")))]
+use scalar_impl as simd_backend;
+
+/// Generate a dynamic pepper using system-specific values
+pub fn generate_dynamic_pepper() -> String {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let process_id = process::id();
+    format!("{:x}-{:x}", time, process_id)
+}
+
+/// Advanced 256-bit hash function
+pub fn advanced_hash(input: &str, salt: &str, pepper: &str) -> [u8; 32] {
+    // Initialize 256-bit state as a single SIMD-lane vector
+    let mut state = simd_backend::initial_state();
+
+    // Combine input, salt, and pepper
+    let combined_input = format!("{}{}{}", pepper, input, salt);
+
+    // Absorb bytes sequentially into the one evolving state (no thread
+    // spawning), so the result is deterministic and order-sensitive.
+    for round in 0..ROUNDS {
+        for byte in combined_input.bytes() {
+            let dynamic_salt = (round as u64).wrapping_add(PRIME as u64) ^ (byte as u64);
+            let mixed_byte = u64::from(byte).wrapping_add(dynamic_salt);
+            simd_backend::hash_round(&mut state, mixed_byte, round);
+        }
+    }
+
+    simd_backend::to_bytes(&state)
+}
+
+/// Number of bytes absorbed into `state` per block.
+pub const BLOCK_SIZE: usize = 32;
+
+/// Streaming, incremental 256-bit hash in the style of the RustCrypto `Digest`
+/// trait: feed data via repeated `update` calls (e.g. from a `BUFFER_SIZE`
+/// read loop over a file), then call `finalize` once to get the digest.
+///
+/// Internally this carries the same `simd_backend::State` vector used by
+/// `advanced_hash` forward across blocks, so the result depends on the
+/// exact byte order and length of everything absorbed rather than being an
+/// order-independent XOR-fold of per-byte work.
+pub struct Mphc256 {
+    state: simd_backend::State,
+    buffer: Vec<u8>,
+    total_len: u64,
+}
+
+impl Mphc256 {
+    pub fn new() -> Self {
+        Mphc256 {
+            state: simd_backend::initial_state(),
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            total_len: 0,
+        }
+    }
+
+    /// Absorb more input bytes, buffering until a full block is available.
+    pub fn update(&mut self, data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+        self.buffer.extend_from_slice(data);
+
+        let mut offset = 0;
+        while self.buffer.len() - offset >= BLOCK_SIZE {
+            Self::absorb_block(&mut self.state, &self.buffer[offset..offset + BLOCK_SIZE]);
+            offset += BLOCK_SIZE;
+        }
+        self.buffer.drain(..offset);
+    }
+
+    /// Run the same `ROUNDS`-deep `hash_round` loop `advanced_hash` used,
+    /// sequentially over the single carried-forward SIMD `state`, so blocks
+    /// compress in order instead of being folded from independent threads.
+    ///
+    /// Takes `state` directly (rather than `&mut self`) so callers can pass
+    /// `&self.buffer` alongside it without a self-borrow conflict.
+    fn absorb_block(state: &mut simd_backend::State, block: &[u8]) {
+        for round in 0..ROUNDS {
+            for &byte in block {
+                let dynamic_salt = (round as u64).wrapping_add(PRIME as u64) ^ (byte as u64);
+                let mixed_byte = u64::from(byte).wrapping_add(dynamic_salt);
+                simd_backend::hash_round(state, mixed_byte, round);
+            }
+        }
+    }
+
+    /// Consume the hasher, absorbing a Merkle-Damgard length-encoding padding
+    /// block (total bytes processed, as in SHA-256) before producing the
+    /// digest so that the output depends on total input length.
+    pub fn finalize(mut self) -> [u8; 32] {
+        if !self.buffer.is_empty() {
+            Self::absorb_block(&mut self.state, &self.buffer);
+        }
+
+        let length_block = self.total_len.to_le_bytes();
+        Self::absorb_block(&mut self.state, &length_block);
+
+        simd_backend::to_bytes(&self.state)
+    }
+}
+
+impl Default for Mphc256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// HMAC inner/outer pad constants, as in the standard HMAC construction.
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// HMAC over MPHC-256, mirroring the HMAC-SHA256 wrapper `moros` uses for
+/// stored credentials: zero-pad `key` to `BLOCK_SIZE` (a key longer than
+/// `BLOCK_SIZE` is first replaced by its digest), XOR with the ipad/opad
+/// constants, and compute `H(opad || H(ipad || message))` using
+/// the streaming digest so messages of any length are supported.
+pub fn hmac_mphc256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block_key = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        let mut hasher = Mphc256::new();
+        hasher.update(key);
+        block_key[..32].copy_from_slice(&hasher.finalize());
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad_key = [0u8; BLOCK_SIZE];
+    let mut opad_key = [0u8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE {
+        ipad_key[i] = block_key[i] ^ IPAD;
+        opad_key[i] = block_key[i] ^ OPAD;
+    }
+
+    let mut inner = Mphc256::new();
+    inner.update(&ipad_key);
+    inner.update(message);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Mphc256::new();
+    outer.update(&opad_key);
+    outer.update(&inner_hash);
+    outer.finalize()
+}
+
+/// Compare two digests in constant time: accumulate the XOR of every byte
+/// rather than returning as soon as a mismatch is found, so the comparison
+/// doesn't leak how many leading bytes matched via timing.
+pub fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let mut diff = 0u8;
+    for i in 0..32 {
+        diff |= a[i] ^ b[i];
+    }
+    diff == 0
+}
+
+/// Re-derive a password hash from `password` and `salt` and compare it
+/// against a previously stored digest, so credentials can actually be
+/// re-verified later instead of only ever being logged.
+pub fn verify(password: &str, salt: &str, expected: &[u8; 32]) -> bool {
+    let recomputed = hmac_mphc256(password.as_bytes(), salt.as_bytes());
+    constant_time_eq(&recomputed, expected)
+}
+
+/// Combine two child digests into their parent, mixing in the tree `depth`
+/// (as an 8-byte little-endian prefix absorbed ahead of `left || right`) so that identical
+/// child pairs at different heights produce different parents, in the
+/// spirit of the Pedersen `hash_combine(depth, left, right)` used in
+/// zcash-sync.
+pub fn hash_combine(depth: u64, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Mphc256::new();
+    hasher.update(&depth.to_le_bytes());
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize()
+}
+
+/// Fold an arbitrary list of 32-byte leaves into a single Merkle root,
+/// combining adjacent nodes level by level and duplicating the last node
+/// when a level has odd length.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+
+    let mut level = leaves.to_vec();
+    let mut depth: u64 = 0;
+    while level.len() > 1 {
+        let mut next_level = Vec::with_capacity(level.len().div_ceil(2));
+        let mut i = 0;
+        while i < level.len() {
+            let left = level[i];
+            let right = if i + 1 < level.len() { level[i + 1] } else { level[i] };
+            next_level.push(hash_combine(depth, &left, &right));
+            i += 2;
+        }
+        level = next_level;
+        depth += 1;
+    }
+
+    level[0]
+}
+
+/// Hash a single file's contents via the streaming digest, reading it in
+/// fixed-size chunks rather than loading it whole.
+pub fn hash_file(path: &Path) -> io::Result<[u8; 32]> {
+    let mut file = File::open(path)?;
+    let mut hasher = Mphc256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Recursively collect the digest of every file under `dir`.
+fn collect_file_hashes(dir: &Path, leaves: &mut Vec<[u8; 32]>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_file_hashes(&path, leaves)?;
+        } else {
+            leaves.push(hash_file(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// Commit to a whole directory with one root hash: hash every file under
+/// `root` and fold the digests into a Merkle root, which the current
+/// single-string `advanced_hash` interface can't express.
+pub fn hash_files_merkle(root: &Path) -> io::Result<[u8; 32]> {
+    let mut leaves = Vec::new();
+    collect_file_hashes(root, &mut leaves)?;
+    leaves.sort();
+    Ok(merkle_root(&leaves))
+}
+
+/// Fixed key for the `Mphc256FastHash` used to index `find_duplicates`'
+/// digest groups. The keys being grouped are already MPHC-256 digests, so
+/// there's no cryptographic reason to re-key per run the way `HashMap`'s
+/// default SipHash does against HashDoS - a fixed key keeps the iteration
+/// order of the groups reproducible across runs.
+const DUPLICATE_GROUPS_HASH_KEY: [u8; 16] = *b"mphc256-dupes-k1";
+
+/// Walk `root` with `walkdir`, hash every file via the streaming digest,
+/// and group files by identical digest, mirroring the `rdupe` tool. Groups
+/// are indexed with `Mphc256FastHash` rather than the default SipHash,
+/// since the keys are already well-distributed MPHC-256 digests.
+///
+/// A single unreadable file (permission denied, removed mid-walk, ...)
+/// shouldn't abort a whole-directory scan any more than an unreadable
+/// directory entry does: both are logged to stderr and skipped.
+pub fn find_duplicates(root: &Path) -> io::Result<HashMap<[u8; 32], Vec<PathBuf>, Mphc256FastHash>> {
+    let mut groups: HashMap<[u8; 32], Vec<PathBuf>, Mphc256FastHash> = HashMap::with_hasher(
+        Mphc256FastHash {
+            key: DUPLICATE_GROUPS_HASH_KEY,
+        },
+    );
+    for entry in WalkDir::new(root) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                eprintln!("skipping: {}", err);
+                continue;
+            }
+        };
+        if entry.file_type().is_file() {
+            match hash_file(entry.path()) {
+                Ok(digest) => {
+                    groups.entry(digest).or_default().push(entry.into_path());
+                }
+                Err(err) => {
+                    eprintln!("skipping {}: {}", entry.path().display(), err);
+                }
+            }
+        }
+    }
+    Ok(groups)
+}
+
+/// Format a digest as a lowercase hex string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Reduced round count for `fast_hash`, versus the full `ROUNDS` used by
+/// `advanced_hash`.
+pub const FAST_ROUNDS: usize = 4;
+
+/// Keyed, non-cryptographic fast hash modeled on Bitcoin's use of
+/// SipHash-2-4 for hashmap/index keys: runs a reduced number of rounds over
+/// a single SIMD state and folds the result down to a `u64`.
+pub fn fast_hash(key: &[u8], data: &[u8]) -> u64 {
+    let mut state = simd_backend::initial_state();
+    for round in 0..FAST_ROUNDS {
+        for &byte in key.iter().chain(data.iter()) {
+            let mixed = u64::from(byte).wrapping_add(round as u64);
+            simd_backend::hash_round(&mut state, mixed, round);
+        }
+    }
+
+    // Every `hash_round` ends by XOR-ing the state vector with a fixed
+    // permutation of its own lanes, which forces the XOR of all four lanes
+    // to 0 after any number of rounds (XOR is permutation-invariant:
+    // lane_0 ^ lane_1 ^ lane_2 ^ lane_3 == perm(lanes)_0 ^ ... ^ perm(lanes)_3,
+    // so XOR-ing them together always cancels). Folding the four lanes
+    // together with a plain XOR would therefore always yield 0; rotating
+    // each lane by a distinct amount first breaks that invariant.
+    let bytes = simd_backend::to_bytes(&state);
+    let mut folded = 0u64;
+    for (i, chunk) in bytes.chunks(8).enumerate() {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        folded ^= word.rotate_left((i as u32) * 17 + 5);
+    }
+    folded
+}
+
+/// `Hasher`/`BuildHasher` wrapper around `fast_hash`, so it can be dropped
+/// into a `HashMap<K, V, Mphc256FastHash>` in place of the default SipHash
+/// implementation.
+pub struct Mphc256FastHasher {
+    key: [u8; 16],
+    buffer: Vec<u8>,
+}
+
+impl Hasher for Mphc256FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        fast_hash(&self.key, &self.buffer)
+    }
+}
+
+#[derive(Clone)]
+pub struct Mphc256FastHash {
+    pub key: [u8; 16],
+}
+
+impl BuildHasher for Mphc256FastHash {
+    type Hasher = Mphc256FastHasher;
+
+    fn build_hasher(&self) -> Mphc256FastHasher {
+        Mphc256FastHasher {
+            key: self.key,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Output encoding for a digest, selectable at runtime instead of the
+/// previously hardcoded Base64.
+#[derive(Clone, Copy, Debug)]
+pub enum Encoding {
+    Hex,
+    Base64,
+    Base58,
+}
+
+/// Base58 alphabet as used by Bitcoin (excludes `0`, `O`, `I`, `l` to avoid
+/// visual ambiguity).
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encode bytes as base58, as in `rust-bitcoin`'s `base58` module.
+pub fn base58_encode(bytes: &[u8]) -> String {
+    let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded: String = "1".repeat(leading_zeros);
+    encoded.extend(digits.iter().rev().map(|&d| BASE58_ALPHABET[d as usize] as char));
+    encoded
+}
+
+/// Format a digest in the requested `Encoding`.
+pub fn format_digest(digest: &[u8; 32], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Hex => to_hex(digest),
+        Encoding::Base64 => encode(digest),
+        Encoding::Base58 => base58_encode(digest),
+    }
+}
+
+/// Parse a `--encoding hex|base64|base58` flag from the CLI args.
+pub fn parse_encoding(args: &[String]) -> Option<Encoding> {
+    let idx = args.iter().position(|a| a == "--encoding")?;
+    match args.get(idx + 1)?.as_str() {
+        "hex" => Some(Encoding::Hex),
+        "base64" => Some(Encoding::Base64),
+        "base58" => Some(Encoding::Base58),
+        _ => None,
+    }
+}
+
+/// Generate a random salt
+pub fn generate_salt() -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(67)
+        .map(char::from)
+        .collect()
+}
+
+/// Convert hash to binary string
+pub fn hash_to_binary_string(hash: &[u8; 32]) -> String {
+    hash.iter()
+        .map(|byte| format!("{:08b}", byte))
+        .collect::<Vec<String>>()
+        .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaming_digest_is_independent_of_chunk_boundaries() {
+        let message = b"the quick brown fox jumps over the lazy dog, repeatedly, to pad this past one block";
+
+        let mut whole = Mphc256::new();
+        whole.update(message);
+        let whole_digest = whole.finalize();
+
+        let mut chunked = Mphc256::new();
+        for chunk in message.chunks(7) {
+            chunked.update(chunk);
+        }
+        let chunked_digest = chunked.finalize();
+
+        assert_eq!(whole_digest, chunked_digest);
+    }
+
+    #[test]
+    fn simd_and_scalar_backends_match() {
+        let mut simd_state = simd_impl::initial_state();
+        let mut scalar_state = scalar_impl::initial_state();
+
+        for round in 0..ROUNDS {
+            for byte in 0..=255u8 {
+                let mixed = u64::from(byte).wrapping_add((round as u64).wrapping_add(PRIME as u64) ^ u64::from(byte));
+                simd_impl::hash_round(&mut simd_state, mixed, round);
+                scalar_impl::hash_round(&mut scalar_state, mixed, round);
+            }
+        }
+
+        assert_eq!(simd_impl::to_bytes(&simd_state), scalar_impl::to_bytes(&scalar_state));
+    }
+
+    #[test]
+    fn hmac_is_deterministic_and_key_sensitive() {
+        let a = hmac_mphc256(b"correct horse", b"some-salt");
+        let b = hmac_mphc256(b"correct horse", b"some-salt");
+        let c = hmac_mphc256(b"wrong horse", b"some-salt");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn hmac_handles_keys_longer_than_block_size() {
+        let long_key = vec![0x42u8; BLOCK_SIZE * 3];
+        let short_key = vec![0x42u8; BLOCK_SIZE - 1];
+        let digest = hmac_mphc256(&long_key, b"message");
+        assert_ne!(digest, hmac_mphc256(&short_key, b"message"));
+    }
+
+    #[test]
+    fn constant_time_eq_matches_memcmp_semantics() {
+        let a = [1u8; 32];
+        let mut b = [1u8; 32];
+        assert!(constant_time_eq(&a, &b));
+
+        b[0] = 2;
+        assert!(!constant_time_eq(&a, &b));
+
+        let mut c = [1u8; 32];
+        c[31] = 2;
+        assert!(!constant_time_eq(&a, &c));
+    }
+
+    #[test]
+    fn verify_accepts_the_right_password_and_rejects_others() {
+        let expected = hmac_mphc256(b"hunter2".as_slice(), b"pepper-salt".as_slice());
+        assert!(verify("hunter2", "pepper-salt", &expected));
+        assert!(!verify("hunter3", "pepper-salt", &expected));
+        assert!(!verify("hunter2", "different-salt", &expected));
+    }
+
+    #[test]
+    fn hash_combine_separates_by_depth() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        let at_depth_0 = hash_combine(0, &left, &right);
+        let at_depth_1 = hash_combine(1, &left, &right);
+        assert_ne!(at_depth_0, at_depth_1);
+    }
+
+    #[test]
+    fn merkle_root_duplicates_the_last_leaf_on_odd_levels() {
+        let a = [1u8; 32];
+        let b = [2u8; 32];
+        let c = [3u8; 32];
+
+        // Three leaves: odd-length level duplicates `c` to pair with itself.
+        let root = merkle_root(&[a, b, c]);
+        let expected_parent_ab = hash_combine(0, &a, &b);
+        let expected_parent_cc = hash_combine(0, &c, &c);
+        let expected_root = hash_combine(1, &expected_parent_ab, &expected_parent_cc);
+        assert_eq!(root, expected_root);
+    }
+
+    #[test]
+    fn merkle_root_of_empty_leaves_is_zero() {
+        assert_eq!(merkle_root(&[]), [0u8; 32]);
+    }
+
+    #[test]
+    fn fast_hash_is_deterministic_and_key_sensitive() {
+        let a = fast_hash(b"key-one", b"some data");
+        let b = fast_hash(b"key-one", b"some data");
+        let c = fast_hash(b"key-two", b"some data");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn fast_hash_backs_a_real_hashmap() {
+        let mut map: HashMap<&str, u32, Mphc256FastHash> = HashMap::with_hasher(Mphc256FastHash {
+            key: *b"0123456789abcdef",
+        });
+        map.insert("alice", 1);
+        map.insert("bob", 2);
+        assert_eq!(map.get("alice"), Some(&1));
+        assert_eq!(map.get("bob"), Some(&2));
+        assert_eq!(map.get("carol"), None);
+    }
+
+    #[test]
+    fn base58_encode_matches_known_vectors() {
+        assert_eq!(base58_encode(&[0x61]), "2g");
+        assert_eq!(base58_encode(&[0x62, 0x62, 0x62]), "a3gV");
+        assert_eq!(base58_encode(&[0x63, 0x63, 0x63]), "aPEr");
+    }
+
+    #[test]
+    fn base58_encode_keeps_one_leading_one_per_leading_zero_byte() {
+        assert_eq!(base58_encode(&[0]), "1");
+        assert_eq!(base58_encode(&[0, 0, 0]), "111");
+        assert_eq!(base58_encode(&[1]), "2");
+    }
+}
@@ -1,115 +1,93 @@
 use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::Path;
-use std::thread;
-use rand::{distributions::Alphanumeric, Rng};
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::process;
-use base64::{encode};
-
-const ROUNDS: usize = 256; // Number of rounds
-const PRIME: u128 = 0x100000001b3; // A large prime number
-
-/// Generate a dynamic pepper using system-specific values
-fn generate_dynamic_pepper() -> String {
-    let time = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    let process_id = process::id();
-    format!("{:x}-{:x}", time, process_id)
-}
-
-/// Modular non-linear transformation
-fn modular_non_linear_transform(value: u128) -> u128 {
-    let rotated = value.rotate_right(23);
-    (value ^ rotated).wrapping_mul(PRIME) % 0xffffffffffffffff
-}
-
-/// Perform a single round of hashing (SIMD-style)
-fn hash_round(
-    state: &mut [u128; 4],
-    mixed_input: u128,
-    round: usize,
-) {
-    for i in 0..4 {
-        state[i] ^= mixed_input; // XOR with input
-        state[i] = state[i].wrapping_add(PRIME); // Add large prime
-        state[i] = state[i].rotate_left((round % 16 + i * 8) as u32); // Rotate bits
-        state[i] = modular_non_linear_transform(state[i]); // Apply non-linear transform
-        state[i] ^= state[(i + 1) % 4]; // Cross-mix state
-    }
-}
 
-/// Advanced 256-bit hash function
-fn advanced_hash(input: &str, salt: &str, pepper: &str) -> [u8; 32] {
-    // Initialize 256-bit state (4 x 64-bit chunks)
-    let mut state: [u128; 4] = [
-        0x6a09e667f3bcc908,
-        0xbb67ae8584caa73b,
-        0x3c6ef372fe94f82b,
-        0xa54ff53a5f1d36f1,
-    ];
-
-    // Combine input, salt, and pepper
-    let combined_input = format!("{}{}{}", pepper, input, salt);
-
-    // Split the combined input into chunks for parallel processing
-    let mut threads = vec![];
-    for round in 0..ROUNDS {
-        let mut local_state = state.clone();
-        for byte in combined_input.bytes() {
-            let dynamic_salt = (round as u128).wrapping_add(PRIME) ^ (local_state[round % 4] & 0xff);
-            let mixed_byte = u128::from(byte).wrapping_add(dynamic_salt);
-
-            // Spawn threads for each state chunk
-            let handle = thread::spawn(move || {
-                let mut chunk_state = local_state;
-                hash_round(&mut chunk_state, mixed_byte, round);
-                chunk_state
-            });
-
-            threads.push(handle);
-        }
-    }
-
-    // Collect results from threads and update state
-    for handle in threads {
-        if let Ok(thread_state) = handle.join() {
-            for i in 0..4 {
-                state[i] ^= thread_state[i];
+use mphc256::{
+    advanced_hash, find_duplicates, format_digest, generate_dynamic_pepper, generate_salt,
+    hash_files_merkle, hash_to_binary_string, hmac_mphc256, parse_encoding, to_hex, verify,
+    Encoding,
+};
+
+/// Run the `dupes` CLI mode: find and print duplicate-content groups under
+/// `root`, so the crate can be used as a content-addressing utility rather
+/// than only an interactive hasher.
+fn run_find_duplicates(root: &Path) -> io::Result<()> {
+    let groups = find_duplicates(root)?;
+    for (digest, paths) in &groups {
+        if paths.len() > 1 {
+            println!("{}:", to_hex(digest));
+            for path in paths {
+                println!("  {}", path.display());
             }
         }
     }
+    Ok(())
+}
 
-    // Convert 4 x u128 chunks to a 256-bit hash
-    let mut final_hash = [0u8; 32];
-    for (i, chunk) in state.iter().enumerate() {
-        // Use only the least significant 8 bytes of each u128
-        final_hash[i * 8..(i + 1) * 8].copy_from_slice(&chunk.to_le_bytes()[..8]);
-    }
+/// Run the `hash-password <password> <salt>` CLI mode: derive and print the
+/// HMAC-MPHC256 digest for a password/salt pair, so it can be stored and
+/// later re-checked with `verify-password`.
+fn run_hash_password(password: &str, salt: &str) {
+    let digest = hmac_mphc256(password.as_bytes(), salt.as_bytes());
+    println!("{}", to_hex(&digest));
+}
 
-    final_hash
+/// Run the `verify-password <password> <salt> <hex-digest>` CLI mode:
+/// recompute the HMAC-MPHC256 digest and compare it in constant time
+/// against a previously stored one, printing whether it matched.
+fn run_verify_password(password: &str, salt: &str, expected_hex: &str) -> io::Result<()> {
+    let expected = parse_hex_digest(expected_hex).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "expected digest must be 64 hex characters",
+        )
+    })?;
+    println!("{}", verify(password, salt, &expected));
+    Ok(())
 }
 
-/// Generate a random salt
-fn generate_salt() -> String {
-    rand::thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(67)
-        .map(char::from)
-        .collect()
+/// Parse a 64-character hex string into a 32-byte digest.
+fn parse_hex_digest(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut digest = [0u8; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(digest)
 }
 
-/// Convert hash to binary string
-fn hash_to_binary_string(hash: &[u8; 32]) -> String {
-    hash.iter()
-        .map(|byte| format!("{:08b}", byte))
-        .collect::<Vec<String>>()
-        .concat()
+/// Run the `merkle <dir>` CLI mode: commit to a whole directory tree with a
+/// single Merkle root, printed as hex.
+fn run_hash_files_merkle(root: &Path) -> io::Result<()> {
+    let root_digest = hash_files_merkle(root)?;
+    println!("{}", to_hex(&root_digest));
+    Ok(())
 }
 
 fn main() -> io::Result<()> {
+    // `dupes <dir>`, `merkle <dir>`, and `hash-password`/`verify-password`
+    // switch into their respective subcommands instead of the interactive
+    // string hasher.
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() >= 3 && args[1] == "dupes" {
+        return run_find_duplicates(Path::new(&args[2]));
+    }
+    if args.len() >= 3 && args[1] == "merkle" {
+        return run_hash_files_merkle(Path::new(&args[2]));
+    }
+    if args.len() >= 4 && args[1] == "hash-password" {
+        run_hash_password(&args[2], &args[3]);
+        return Ok(());
+    }
+    if args.len() >= 5 && args[1] == "verify-password" {
+        return run_verify_password(&args[2], &args[3], &args[4]);
+    }
+
+    // Digest output encoding, selectable via `--encoding hex|base64|base58`.
+    let encoding = parse_encoding(&args).unwrap_or(Encoding::Base64);
+
     // Define the folder and log file paths
     let folder_path = "logs";
     let log_file_path = format!("{}/hash_log.txt", folder_path);
@@ -148,21 +126,21 @@ fn main() -> io::Result<()> {
         // Convert hash to binary string
         let binary_hash = hash_to_binary_string(&hash_result);
 
-        // Encode the hash result in Base64
-        let base64_hash = encode(&hash_result);
+        // Encode the hash result in the selected encoding
+        let digest_str = format_digest(&hash_result, encoding);
 
         // Log the input, salt, pepper, and hash details to the file
         writeln!(
             log_file,
-            "Input: '{}'\nSalt: '{}'\nPepper: '{}'\nBinary Hash: {}\nBase64 Hash: {}\n",
-            input_string, salt, pepper, binary_hash, base64_hash
+            "Input: '{}'\nSalt: '{}'\nPepper: '{}'\nBinary Hash: {}\nDigest ({:?}): {}\n",
+            input_string, salt, pepper, binary_hash, encoding, digest_str
         )?;
 
         // Add an empty line between log entries for better readability
-        writeln!(log_file, "")?;
+        writeln!(log_file)?;
 
-        // Print only the Base64 hash in the console
-        println!("(Base64): {}", base64_hash);
+        // Print only the encoded digest in the console
+        println!("({:?}): {}", encoding, digest_str);
     }
 
     println!("Exiting. Log saved to '{}'.", log_file_path);